@@ -1,10 +1,42 @@
-use actix_web::{dev::ServiceRequest, Error};
+use actix_web::{dev::ServiceRequest, Error, HttpResponse, ResponseError};
 use futures_util::future::{ok, Ready};
 use futures_util::FutureExt;
-use std::task::{Context, Poll};
 use std::env;
+use std::task::{Context, Poll};
+use subtle::ConstantTimeEq;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum AuthError {
+    #[error("invalid or missing API key")]
+    InvalidApiKey,
+}
+
+impl ResponseError for AuthError {
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::Unauthorized()
+            .content_type("application/json")
+            .json(serde_json::json!({ "error": "invalid_api_key" }))
+    }
+}
+
+pub struct ApiKeyMiddleware {
+    expected_api_key: String,
+}
 
-pub struct ApiKeyMiddleware;
+impl ApiKeyMiddleware {
+    pub fn new() -> Self {
+        Self {
+            expected_api_key: env::var("ADMIN_API_KEY").expect("ADMIN_API_KEY must be set"),
+        }
+    }
+}
+
+impl Default for ApiKeyMiddleware {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl<S, B> actix_service::Transform<S, ServiceRequest> for ApiKeyMiddleware
 where
@@ -18,12 +50,27 @@ where
     type Future = Ready<Result<Self::Transform, Self::InitError>>;
 
     fn new_transform(&self, service: S) -> Self::Future {
-        ok(ApiKeyMiddlewareService { service })
+        ok(ApiKeyMiddlewareService {
+            service,
+            expected_api_key: self.expected_api_key.clone(),
+        })
     }
 }
 
 pub struct ApiKeyMiddlewareService<S> {
     service: S,
+    expected_api_key: String,
+}
+
+/// Compares two byte strings in constant time with respect to their
+/// contents, so a valid key's length doesn't leak via early-exit timing.
+/// Both sides are hashed to fixed-length SHA-256 digests first, and the
+/// digests are compared with `ConstantTimeEq`.
+pub(crate) fn keys_match(provided: &[u8], expected: &[u8]) -> bool {
+    use sha2::{Digest, Sha256};
+    let provided_digest = Sha256::digest(provided);
+    let expected_digest = Sha256::digest(expected);
+    provided_digest.as_slice().ct_eq(expected_digest.as_slice()).into()
 }
 
 impl<S, B> actix_service::Service<ServiceRequest> for ApiKeyMiddlewareService<S>
@@ -40,18 +87,23 @@ where
     }
 
     fn call(&self, req: ServiceRequest) -> Self::Future {
-        let api_key = req.headers().get("x-api-key").cloned();
+        let provided_key = req
+            .headers()
+            .get("x-api-key")
+            .and_then(|v| v.to_str().ok())
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_owned());
+        let expected_api_key = self.expected_api_key.clone();
         let fut = self.service.call(req);
 
         async move {
-            if let Some(api_key) = api_key {
-                let expected_api_key = env::var("ADMIN_API_KEY").expect("ADMIN_API_KEY must be set");
-                if api_key.to_str().unwrap_or("") == expected_api_key {
-                    return fut.await;
+            match provided_key {
+                Some(provided) if keys_match(provided.as_bytes(), expected_api_key.as_bytes()) => {
+                    fut.await
                 }
+                _ => Err(AuthError::InvalidApiKey.into()),
             }
-            Err(actix_web::error::ErrorUnauthorized("Invalid API key")).into()
         }
         .boxed_local()
     }
-}
\ No newline at end of file
+}
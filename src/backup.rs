@@ -1,24 +1,71 @@
+use actix_web::{get, http::StatusCode, post, web::Data, web::Path as WebPath, HttpResponse, ResponseError};
+use async_trait::async_trait;
 use aws_config::{meta::region::RegionProviderChain, BehaviorVersion, Region};
 use aws_sdk_s3::error::SdkError as AwsSdkError;
 use aws_sdk_s3::{primitives::ByteStream, Client as S3Client};
-use chrono::{Duration, TimeZone, Utc};
+use base64::Engine as _;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use sha2::{Digest, Sha256};
 use std::{
+    collections::{HashMap, HashSet},
     error::Error as StdError,
     io::Cursor,
+    path::{Path, PathBuf},
     sync::{Arc, Mutex},
 };
 use thiserror::Error;
+use tokio::io::AsyncReadExt;
 use tokio::time::interval;
 
 use crate::messages::Message;
 
+/// Wraps a `Write`, tallying the bytes passed through it and hashing them
+/// with SHA-256, so `perform_backup` can report the uncompressed size and a
+/// checksum without buffering the serialized JSON in memory first.
+struct CountingWriter<W> {
+    inner: W,
+    count: usize,
+    hasher: Sha256,
+}
+
+impl<W: std::io::Write> CountingWriter<W> {
+    fn new(inner: W) -> Self {
+        Self {
+            inner,
+            count: 0,
+            hasher: Sha256::new(),
+        }
+    }
+
+    fn into_inner(self) -> W {
+        self.inner
+    }
+
+    fn checksum_hex(&self) -> String {
+        format!("{:x}", self.hasher.clone().finalize())
+    }
+}
+
+impl<W: std::io::Write> std::io::Write for CountingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.count += written;
+        self.hasher.update(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum BackupError {
     #[error("AWS SDK error: {0}")]
     AwsError(#[from] aws_sdk_s3::Error),
 
-    #[error("Compression error: {0}")]
-    CompressionError(#[from] std::io::Error),
+    #[error("I/O error: {0}")]
+    IoError(#[from] std::io::Error),
 
     #[error("Serialization Error: {0}")]
     SerializationError(#[from] serde_json::Error),
@@ -29,6 +76,28 @@ pub enum BackupError {
     #[error("AWS operation error: {0}")]
     AwsOperationError(String),
 
+    #[error("backup not found: {0}")]
+    NotFound(String),
+
+    #[error("BACKUP_ENCRYPTION_KEY must be 32 base64-encoded bytes")]
+    InvalidEncryptionKey,
+
+    #[error("backup encryption/decryption failed")]
+    EncryptionError,
+
+    #[error("backup {key} failed checksum verification: expected {expected}, got {actual}")]
+    ChecksumMismatch {
+        key: String,
+        expected: String,
+        actual: String,
+    },
+
+    #[error("failed to acquire lock on message repo")]
+    LockPoisoned,
+
+    #[error("invalid backup key: {0}")]
+    InvalidKey(String),
+
     #[error("Unknown error: {0}")]
     Unknown(#[from] Box<dyn StdError + Send + Sync>),
 }
@@ -42,7 +111,398 @@ where
     }
 }
 
-#[derive(Clone, Debug)]
+impl ResponseError for BackupError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            BackupError::NotFound(_) => StatusCode::NOT_FOUND,
+            BackupError::InvalidKey(_) => StatusCode::BAD_REQUEST,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code())
+            .json(serde_json::json!({ "error": self.to_string() }))
+    }
+}
+
+/// A single stored backup object as reported by a `BackupBackend`, along
+/// with the metadata `perform_backup` wrote alongside it.
+#[derive(Debug, Clone)]
+pub struct BackupObject {
+    pub key: String,
+    pub last_modified: DateTime<Utc>,
+    pub metadata: HashMap<String, String>,
+}
+
+/// Storage target for backup blobs. Lets `BackupSystem` stay agnostic to
+/// whether backups land in AWS S3, an S3-compatible store (MinIO, Garage),
+/// or a local directory.
+#[async_trait]
+pub trait BackupBackend: Send + Sync {
+    /// Uploads the file at `path` under `key`. Backends read it from disk
+    /// rather than taking an in-memory buffer so a backup's peak memory
+    /// usage doesn't scale with the size of the message set.
+    async fn put(
+        &self,
+        key: &str,
+        path: &Path,
+        metadata: HashMap<String, String>,
+    ) -> Result<(), BackupError>;
+
+    async fn list(&self, prefix: &str) -> Result<Vec<BackupObject>, BackupError>;
+
+    async fn delete(&self, key: &str) -> Result<(), BackupError>;
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>, BackupError>;
+
+    /// Fetches the metadata `put` wrote for `key`, without downloading the
+    /// object body. Returns an empty map for backups written before a given
+    /// metadata field existed.
+    async fn head(&self, key: &str) -> Result<HashMap<String, String>, BackupError>;
+}
+
+/// Backend that stores backups in an AWS S3 bucket or any S3-compatible
+/// store reachable via a custom endpoint (MinIO, Garage, ...).
+pub struct S3Backend {
+    client: S3Client,
+    bucket: String,
+}
+
+impl S3Backend {
+    pub async fn new(config: &BackupConfig) -> Result<Self, BackupError> {
+        let region = Region::new(config.region.clone());
+        let region_provider = RegionProviderChain::first_try(region).or_default_provider();
+        let sdk_config = aws_config::defaults(BehaviorVersion::latest())
+            .region(region_provider)
+            .load()
+            .await;
+
+        let mut s3_config_builder =
+            aws_sdk_s3::config::Builder::from(&sdk_config).force_path_style(config.force_path_style);
+        if let Some(endpoint_url) = &config.endpoint_url {
+            s3_config_builder = s3_config_builder.endpoint_url(endpoint_url);
+        }
+
+        let client = S3Client::from_conf(s3_config_builder.build());
+
+        Ok(Self {
+            client,
+            bucket: config.bucket_name.clone(),
+        })
+    }
+}
+
+const MULTIPART_CHUNK_SIZE: usize = 8 * 1024 * 1024;
+
+#[async_trait]
+impl BackupBackend for S3Backend {
+    async fn put(
+        &self,
+        key: &str,
+        path: &Path,
+        metadata: HashMap<String, String>,
+    ) -> Result<(), BackupError> {
+        let mut create_request = self
+            .client
+            .create_multipart_upload()
+            .bucket(&self.bucket)
+            .key(key)
+            .content_type("application/zstd")
+            .storage_class(aws_sdk_s3::types::StorageClass::StandardIa);
+
+        for (name, value) in &metadata {
+            create_request = create_request.metadata(name, value);
+        }
+
+        let create_output = create_request.send().await.map_err(BackupError::from)?;
+        let upload_id = create_output
+            .upload_id()
+            .ok_or_else(|| BackupError::AwsOperationError("multipart upload missing an id".to_string()))?
+            .to_string();
+
+        let mut file = tokio::fs::File::open(path).await?;
+        let mut parts = Vec::new();
+        let mut part_number: i32 = 1;
+        let mut buffer = vec![0u8; MULTIPART_CHUNK_SIZE];
+
+        loop {
+            let mut filled = 0;
+            while filled < buffer.len() {
+                let read = file.read(&mut buffer[filled..]).await?;
+                if read == 0 {
+                    break;
+                }
+                filled += read;
+            }
+            if filled == 0 {
+                break;
+            }
+
+            let upload_part_output = self
+                .client
+                .upload_part()
+                .bucket(&self.bucket)
+                .key(key)
+                .upload_id(&upload_id)
+                .part_number(part_number)
+                .body(ByteStream::from(buffer[..filled].to_vec()))
+                .send()
+                .await
+                .map_err(BackupError::from)?;
+
+            parts.push(
+                aws_sdk_s3::types::CompletedPart::builder()
+                    .part_number(part_number)
+                    .e_tag(upload_part_output.e_tag().unwrap_or_default())
+                    .build(),
+            );
+            part_number += 1;
+
+            if filled < buffer.len() {
+                break;
+            }
+        }
+
+        self.client
+            .complete_multipart_upload()
+            .bucket(&self.bucket)
+            .key(key)
+            .upload_id(&upload_id)
+            .multipart_upload(
+                aws_sdk_s3::types::CompletedMultipartUpload::builder()
+                    .set_parts(Some(parts))
+                    .build(),
+            )
+            .send()
+            .await
+            .map_err(BackupError::from)?;
+
+        Ok(())
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<BackupObject>, BackupError> {
+        let objects = self
+            .client
+            .list_objects_v2()
+            .bucket(&self.bucket)
+            .prefix(prefix)
+            .send()
+            .await
+            .map_err(BackupError::from)?;
+
+        let keys_and_dates: Vec<(String, DateTime<Utc>)> = objects
+            .contents()
+            .iter()
+            .filter_map(|object| {
+                let key = object.key()?.to_string();
+                let millis = object.last_modified()?.to_millis().ok()?;
+                let last_modified = Utc.timestamp_millis_opt(millis).single()?;
+                Some((key, last_modified))
+            })
+            .collect();
+
+        let mut results = Vec::with_capacity(keys_and_dates.len());
+        for (key, last_modified) in keys_and_dates {
+            let metadata = self.head(&key).await?;
+            results.push(BackupObject {
+                key,
+                last_modified,
+                metadata,
+            });
+        }
+
+        Ok(results)
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), BackupError> {
+        self.client
+            .delete_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .send()
+            .await
+            .map_err(BackupError::from)?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>, BackupError> {
+        let response = self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .send()
+            .await
+            .map_err(BackupError::from)?;
+
+        let bytes = response
+            .body
+            .collect()
+            .await
+            .map_err(|e| BackupError::Unknown(Box::new(e)))?
+            .into_bytes();
+
+        Ok(bytes.to_vec())
+    }
+
+    async fn head(&self, key: &str) -> Result<HashMap<String, String>, BackupError> {
+        let response = self
+            .client
+            .head_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .send()
+            .await
+            .map_err(BackupError::from)?;
+
+        Ok(response.metadata().cloned().unwrap_or_default())
+    }
+}
+
+/// Backend that stores backups as plain files under a local directory, for
+/// self-hosted setups that don't want to run an S3-compatible store.
+pub struct FilesystemBackend {
+    root: PathBuf,
+}
+
+impl FilesystemBackend {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Joins `key` onto the backup root, rejecting `..` components or
+    /// absolute paths so a caller-supplied key (e.g. from the admin restore
+    /// route) can't escape the configured backup directory.
+    fn path_for(&self, key: &str) -> Result<PathBuf, BackupError> {
+        let key_path = Path::new(key);
+        if key_path.is_absolute()
+            || key_path
+                .components()
+                .any(|c| matches!(c, std::path::Component::ParentDir))
+        {
+            return Err(BackupError::InvalidKey(key.to_string()));
+        }
+        Ok(self.root.join(key_path))
+    }
+
+    /// Metadata sidecar path for `key`. The filesystem backend has nowhere
+    /// else to stash `put`'s metadata map, so it's written out next to the
+    /// backup file itself.
+    fn meta_path_for(&self, key: &str) -> Result<PathBuf, BackupError> {
+        let mut path = self.path_for(key)?.into_os_string();
+        path.push(".meta.json");
+        Ok(path.into())
+    }
+}
+
+#[async_trait]
+impl BackupBackend for FilesystemBackend {
+    async fn put(
+        &self,
+        key: &str,
+        path: &Path,
+        metadata: HashMap<String, String>,
+    ) -> Result<(), BackupError> {
+        let dest = self.path_for(key)?;
+        if let Some(parent) = dest.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::copy(path, &dest).await?;
+        tokio::fs::write(self.meta_path_for(key)?, serde_json::to_vec(&metadata)?).await?;
+        Ok(())
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<BackupObject>, BackupError> {
+        let dir = self.root.join(prefix);
+        let mut read_dir = match tokio::fs::read_dir(&dir).await {
+            Ok(read_dir) => read_dir,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut objects = Vec::new();
+        while let Some(entry) = read_dir.next_entry().await? {
+            let file_name = entry.file_name().to_string_lossy().into_owned();
+            if file_name.ends_with(".meta.json") {
+                continue;
+            }
+
+            let metadata = entry.metadata().await?;
+            let last_modified: DateTime<Utc> = metadata.modified()?.into();
+            let key = format!("{}/{}", prefix, file_name);
+            objects.push(BackupObject {
+                metadata: self.head(&key).await?,
+                key,
+                last_modified,
+            });
+        }
+
+        Ok(objects)
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), BackupError> {
+        let path = self.path_for(key)?;
+        match tokio::fs::remove_file(&path).await {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+
+        match tokio::fs::remove_file(self.meta_path_for(key)?).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>, BackupError> {
+        let path = self.path_for(key)?;
+        match tokio::fs::read(&path).await {
+            Ok(bytes) => Ok(bytes),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Err(BackupError::NotFound(key.to_string())),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn head(&self, key: &str) -> Result<HashMap<String, String>, BackupError> {
+        match tokio::fs::read(self.meta_path_for(key)?).await {
+            Ok(bytes) => Ok(serde_json::from_slice(&bytes)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(HashMap::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// Whether each interval writes a full snapshot or diffs against the last
+/// manifest and uploads only the messages that changed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BackupMode {
+    Full,
+    Incremental,
+}
+
+impl BackupMode {
+    fn from_env() -> Self {
+        match std::env::var("BACKUP_MODE").unwrap_or_default().to_lowercase().as_str() {
+            "incremental" => BackupMode::Incremental,
+            _ => BackupMode::Full,
+        }
+    }
+}
+
+/// A snapshot of the message set as an ordered list of chunk hashes. Each
+/// manifest lists every chunk of its snapshot, so restore and cleanup read
+/// manifests on their own; `previous_manifest_key` records the manifest
+/// this one was diffed against and is not followed by either.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+struct Manifest {
+    timestamp: DateTime<Utc>,
+    chunk_hashes: Vec<String>,
+    previous_manifest_key: Option<String>,
+}
+
+#[derive(Clone)]
 pub struct BackupConfig {
     pub bucket_name: String,
     pub prefix: String,
@@ -50,10 +510,41 @@ pub struct BackupConfig {
     pub retention_days: i64,
     pub backup_interval_hours: u64,
     pub compression_level: i32,
+    pub endpoint_url: Option<String>,
+    pub force_path_style: bool,
+    pub local_path: Option<String>,
+    pub mode: BackupMode,
+    pub encrypted: bool,
+    encryption_key: Option<[u8; 32]>,
+}
+
+impl std::fmt::Debug for BackupConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BackupConfig")
+            .field("bucket_name", &self.bucket_name)
+            .field("prefix", &self.prefix)
+            .field("region", &self.region)
+            .field("retention_days", &self.retention_days)
+            .field("backup_interval_hours", &self.backup_interval_hours)
+            .field("compression_level", &self.compression_level)
+            .field("endpoint_url", &self.endpoint_url)
+            .field("force_path_style", &self.force_path_style)
+            .field("local_path", &self.local_path)
+            .field("mode", &self.mode)
+            .field("encrypted", &self.encrypted)
+            .field("encryption_key", &self.encryption_key.as_ref().map(|_| "<redacted>"))
+            .finish()
+    }
 }
 
 impl BackupConfig {
     pub fn from_env() -> Result<Self, BackupError> {
+        let encryption_key = match std::env::var("BACKUP_ENCRYPTION_KEY") {
+            Ok(value) => Some(parse_encryption_key(&value)?),
+            Err(std::env::VarError::NotPresent) => None,
+            Err(e) => return Err(e.into()),
+        };
+
         Ok(Self {
             bucket_name: std::env::var("AWS_BACKUP_BUCKET")?,
             prefix: std::env::var("AWS_BACKUP_PREFIX")
@@ -71,10 +562,63 @@ impl BackupConfig {
                 .unwrap_or_else(|_| "3".to_string())
                 .parse()
                 .unwrap_or(3),
+            endpoint_url: std::env::var("AWS_BACKUP_ENDPOINT_URL").ok(),
+            force_path_style: std::env::var("AWS_BACKUP_FORCE_PATH_STYLE")
+                .map(|v| v == "true")
+                .unwrap_or(false),
+            local_path: std::env::var("BACKUP_LOCAL_PATH").ok(),
+            mode: BackupMode::from_env(),
+            encrypted: encryption_key.is_some(),
+            encryption_key,
         })
     }
 }
 
+fn parse_encryption_key(value: &str) -> Result<[u8; 32], BackupError> {
+    let decoded = base64::engine::general_purpose::STANDARD
+        .decode(value.trim())
+        .map_err(|_| BackupError::InvalidEncryptionKey)?;
+    decoded.try_into().map_err(|_| BackupError::InvalidEncryptionKey)
+}
+
+/// Encrypts `plaintext` with AES-256-GCM under a fresh random nonce,
+/// returning `nonce || ciphertext || tag`.
+pub(crate) fn encrypt_blob(key: &[u8; 32], plaintext: &[u8]) -> Result<Vec<u8>, BackupError> {
+    use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+    use aes_gcm::{Aes256Gcm, Key};
+
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext)
+        .map_err(|_| BackupError::EncryptionError)?;
+
+    let mut blob = Vec::with_capacity(nonce.len() + ciphertext.len());
+    blob.extend_from_slice(&nonce);
+    blob.extend_from_slice(&ciphertext);
+    Ok(blob)
+}
+
+/// Reverses [`encrypt_blob`], verifying the AEAD tag before returning the
+/// plaintext exactly as it was passed in (still zstd-compressed for backup
+/// blobs and chunks).
+pub(crate) fn decrypt_blob(key: &[u8; 32], blob: &[u8]) -> Result<Vec<u8>, BackupError> {
+    use aes_gcm::aead::{Aead, KeyInit};
+    use aes_gcm::{Aes256Gcm, Key, Nonce};
+
+    const NONCE_LEN: usize = 12;
+    if blob.len() < NONCE_LEN {
+        return Err(BackupError::EncryptionError);
+    }
+    let (nonce_bytes, ciphertext) = blob.split_at(NONCE_LEN);
+
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+    let nonce = Nonce::from_slice(nonce_bytes);
+    cipher
+        .decrypt(nonce, ciphertext)
+        .map_err(|_| BackupError::EncryptionError)
+}
+
 pub struct BackupMetrics {
     pub original_size: usize,
     pub compressed_size: usize,
@@ -85,7 +629,7 @@ pub struct BackupMetrics {
 pub struct BackupSystem {
     messages: Arc<Mutex<Vec<Message>>>,
     config: BackupConfig,
-    client: S3Client,
+    backend: Box<dyn BackupBackend>,
 }
 
 impl BackupSystem {
@@ -93,23 +637,28 @@ impl BackupSystem {
         messages: Arc<Mutex<Vec<Message>>>,
         config: BackupConfig,
     ) -> Result<Self, BackupError> {
-        let region = Region::new(config.region.clone());
-        let region_provider = RegionProviderChain::first_try(region).or_default_provider();
-        let sdk_config = aws_config::defaults(BehaviorVersion::latest())
-            .region(region_provider)
-            .load()
-            .await;
-
-        let client = S3Client::new(&sdk_config);
+        let backend: Box<dyn BackupBackend> = match std::env::var("BACKUP_BACKEND")
+            .unwrap_or_else(|_| "s3".to_string())
+            .as_str()
+        {
+            "filesystem" | "local" => {
+                let root = config
+                    .local_path
+                    .clone()
+                    .unwrap_or_else(|| "./backups".to_string());
+                Box::new(FilesystemBackend::new(root))
+            }
+            _ => Box::new(S3Backend::new(&config).await?),
+        };
 
         Ok(Self {
             messages,
             config,
-            client,
+            backend,
         })
     }
 
-    pub async fn start_backup_task(self) {
+    pub async fn start_backup_task(self: Arc<Self>) {
         let interval_secs = self.config.backup_interval_hours * 3600;
         let mut interval = interval(tokio::time::Duration::from_secs(interval_secs));
 
@@ -143,44 +692,108 @@ impl BackupSystem {
     }
 
     async fn perform_backup(&self) -> Result<BackupMetrics, BackupError> {
+        match self.config.mode {
+            BackupMode::Full => self.perform_full_backup().await,
+            BackupMode::Incremental => self.perform_incremental_backup().await,
+        }
+    }
+
+    /// Decrypts `bytes` if `key`'s own `"encrypted"` metadata (written by
+    /// the backup path that uploaded it) says so — not the *current*
+    /// `config.encryption_key`, so restoring an older backup still works
+    /// after encryption is turned on/off or the key is rotated.
+    async fn decrypt_if_needed(&self, key: &str, bytes: Vec<u8>) -> Result<Vec<u8>, BackupError> {
+        let was_encrypted = self
+            .backend
+            .head(key)
+            .await?
+            .get("encrypted")
+            .map(|v| v == "true")
+            .unwrap_or(false);
+
+        if !was_encrypted {
+            return Ok(bytes);
+        }
+
+        let encryption_key = self
+            .config
+            .encryption_key
+            .as_ref()
+            .ok_or(BackupError::EncryptionError)?;
+        decrypt_blob(encryption_key, &bytes)
+    }
+
+    /// If an encryption key is configured, encrypts the file at `path` into
+    /// a fresh temp file and returns it alongside its encrypted size; the
+    /// caller uploads that file instead of `path`. Shared by the full and
+    /// incremental backup paths so neither can silently skip encryption.
+    /// The whole file is read into memory and encrypted in one piece, so
+    /// with encryption enabled peak memory grows with the file's size.
+    async fn encrypt_for_upload(
+        &self,
+        path: &Path,
+    ) -> Result<Option<(tempfile::NamedTempFile, usize)>, BackupError> {
+        let Some(encryption_key) = &self.config.encryption_key else {
+            return Ok(None);
+        };
+
+        let plaintext = tokio::fs::read(path).await?;
+        let encrypted_bytes = encrypt_blob(encryption_key, &plaintext)?;
+        let encrypted_temp_file = tempfile::NamedTempFile::new()?;
+        tokio::fs::write(encrypted_temp_file.path(), &encrypted_bytes).await?;
+        let encrypted_size = encrypted_bytes.len();
+        Ok(Some((encrypted_temp_file, encrypted_size)))
+    }
+
+    async fn perform_full_backup(&self) -> Result<BackupMetrics, BackupError> {
         let timestamp = Utc::now().format("%Y%m%d_%H%M%S");
         let key = format!("{}/backup_{}.json.zst", self.config.prefix, timestamp);
 
-        // Serialize messages in a separate scope so the lock is dropped
-        let (json, original_size, message_count) = {
+        // Serialize straight into a zstd-compressing writer over a temp
+        // file instead of holding the full JSON and compressed buffer at
+        // once. Without encryption, peak memory stays flat regardless of
+        // message volume; `encrypt_for_upload` loads the compressed file.
+        let temp_file = tempfile::NamedTempFile::new()?;
+        let encoder = zstd::stream::Encoder::new(temp_file.reopen()?, self.config.compression_level)?;
+        let mut counting_writer = CountingWriter::new(encoder);
+
+        let compression_start = std::time::Instant::now();
+        let message_count = {
             let messages = self.messages.lock().unwrap();
-            let original_size = messages.len() * std::mem::size_of::<Message>();
-            let message_count = messages.len();
-            let json = serde_json::to_string(&*messages)?;
-            (json, original_size, message_count)
+            serde_json::to_writer(&mut counting_writer, &*messages)?;
+            messages.len()
         };
 
-        let compression_start = std::time::Instant::now();
-        let compressed =
-            zstd::stream::encode_all(Cursor::new(json.as_bytes()), self.config.compression_level)?;
+        let original_size = counting_writer.count;
+        let checksum = counting_writer.checksum_hex();
+        counting_writer.into_inner().finish()?;
         let compression_time = compression_start.elapsed();
-        let compressed_size = compressed.len();
+        let compressed_size = temp_file.as_file().metadata()?.len() as usize;
+
+        let mut metadata = HashMap::from([
+            ("original_size".to_string(), original_size.to_string()),
+            ("compressed_size".to_string(), compressed_size.to_string()),
+            ("message_count".to_string(), message_count.to_string()),
+            ("encrypted".to_string(), self.config.encrypted.to_string()),
+            ("checksum_sha256".to_string(), checksum),
+        ]);
+
+        // If the operator configured an encryption key, encrypt the
+        // compressed blob before it ever leaves the process.
+        let encrypted_temp_file = self.encrypt_for_upload(temp_file.path()).await?;
+        if let Some((_, encrypted_size)) = &encrypted_temp_file {
+            metadata.insert("encrypted_size".to_string(), encrypted_size.to_string());
+        }
+        let upload_path = encrypted_temp_file
+            .as_ref()
+            .map(|(f, _)| f.path())
+            .unwrap_or_else(|| temp_file.path());
 
-        // Upload to S3
         let upload_start = std::time::Instant::now();
-        self.client
-            .put_object()
-            .bucket(&self.config.bucket_name)
-            .key(&key)
-            .body(ByteStream::from(compressed))
-            .content_type("application/zstd+bincode")
-            .storage_class(aws_sdk_s3::types::StorageClass::StandardIa)
-            .metadata("original_size", original_size.to_string())
-            .metadata("compressed_size", compressed_size.to_string())
-            .metadata("message_count", message_count.to_string())
-            .send()
-            .await
-            .map_err(BackupError::from)?;
+        self.backend.put(&key, upload_path, metadata).await?;
         let upload_time = upload_start.elapsed();
 
-        self.cleanup_old_backups()
-            .await
-            .map_err(|e| BackupError::Unknown(e.to_string().into()))?;
+        self.cleanup_old_backups().await?;
 
         Ok(BackupMetrics {
             original_size,
@@ -190,61 +803,370 @@ impl BackupSystem {
         })
     }
 
-    async fn cleanup_old_backups(&self) -> Result<(), Box<dyn StdError>> {
+    async fn cleanup_old_backups(&self) -> Result<(), BackupError> {
+        match self.config.mode {
+            BackupMode::Full => self.cleanup_full_backups().await,
+            BackupMode::Incremental => self.cleanup_incremental_backups().await,
+        }
+    }
+
+    async fn cleanup_full_backups(&self) -> Result<(), BackupError> {
         let cutoff_date = Utc::now() - Duration::days(self.config.retention_days);
 
-        let objects = self
-            .client
-            .list_objects_v2()
-            .bucket(&self.config.bucket_name)
-            .prefix(&self.config.prefix)
-            .send()
-            .await?;
+        let objects = self.backend.list(&self.config.prefix).await?;
 
-        for object in objects.contents() {
-            if let (Some(key), Some(last_modified)) = (object.key(), object.last_modified()) {
-                // Handle the Result from to_millis()
-                if let Ok(millis) = last_modified.to_millis() {
-                    let last_modified = Utc.timestamp_millis_opt(millis).unwrap();
-
-                    if last_modified < cutoff_date {
-                        self.client
-                            .delete_object()
-                            .bucket(&self.config.bucket_name)
-                            .key(key)
-                            .send()
-                            .await?;
-                    }
-                }
+        for object in objects {
+            if object.last_modified < cutoff_date {
+                self.backend.delete(&object.key).await?;
             }
         }
 
         Ok(())
     }
 
-    #[allow(dead_code)]
-    pub async fn restore_from_backup(
-        &self,
-        backup_key: &str,
-    ) -> Result<Vec<Message>, Box<dyn std::error::Error>> {
-        // Download the backup from S3
-        let response = self
-            .client
-            .get_object()
-            .bucket(&self.config.bucket_name)
-            .key(backup_key)
-            .send()
+    fn manifests_prefix(&self) -> String {
+        format!("{}/manifests", self.config.prefix)
+    }
+
+    fn chunks_prefix(&self) -> String {
+        format!("{}/chunks", self.config.prefix)
+    }
+
+    async fn latest_manifest(&self) -> Result<Option<(String, Manifest)>, BackupError> {
+        let mut objects = self.backend.list(&self.manifests_prefix()).await?;
+        objects.sort_by_key(|object| object.last_modified);
+
+        let latest = match objects.pop() {
+            Some(object) => object,
+            None => return Ok(None),
+        };
+
+        let bytes = self
+            .decrypt_if_needed(&latest.key, self.backend.get(&latest.key).await?)
+            .await?;
+        let manifest: Manifest = serde_json::from_slice(&bytes)?;
+        Ok(Some((latest.key, manifest)))
+    }
+
+    async fn perform_incremental_backup(&self) -> Result<BackupMetrics, BackupError> {
+        let messages_snapshot: Vec<Message> = {
+            let messages = self.messages.lock().unwrap();
+            messages.clone()
+        };
+        let message_count = messages_snapshot.len();
+
+        let previous = self.latest_manifest().await?;
+        let previous_hashes: HashSet<String> = previous
+            .as_ref()
+            .map(|(_, manifest)| manifest.chunk_hashes.iter().cloned().collect())
+            .unwrap_or_default();
+
+        let compression_start = std::time::Instant::now();
+        let mut chunk_hashes = Vec::with_capacity(message_count);
+        let mut original_size = 0usize;
+        let mut compressed_size = 0usize;
+
+        for message in &messages_snapshot {
+            let json = serde_json::to_vec(message)?;
+            original_size += json.len();
+
+            let mut hasher = Sha256::new();
+            hasher.update(&json);
+            let hash = format!("{:x}", hasher.finalize());
+
+            if !previous_hashes.contains(&hash) {
+                let compressed = zstd::stream::encode_all(Cursor::new(json.as_slice()), self.config.compression_level)?;
+                compressed_size += compressed.len();
+
+                let temp_file = tempfile::NamedTempFile::new()?;
+                tokio::fs::write(temp_file.path(), &compressed).await?;
+
+                let mut chunk_metadata =
+                    HashMap::from([("encrypted".to_string(), self.config.encrypted.to_string())]);
+                let encrypted_temp_file = self.encrypt_for_upload(temp_file.path()).await?;
+                if let Some((_, encrypted_size)) = &encrypted_temp_file {
+                    chunk_metadata.insert("encrypted_size".to_string(), encrypted_size.to_string());
+                }
+                let chunk_upload_path = encrypted_temp_file
+                    .as_ref()
+                    .map(|(f, _)| f.path())
+                    .unwrap_or_else(|| temp_file.path());
+
+                let chunk_key = format!("{}/{}.zst", self.chunks_prefix(), hash);
+                self.backend.put(&chunk_key, chunk_upload_path, chunk_metadata).await?;
+            }
+
+            chunk_hashes.push(hash);
+        }
+        let compression_time = compression_start.elapsed();
+
+        let timestamp = Utc::now();
+        let manifest = Manifest {
+            timestamp,
+            chunk_hashes,
+            previous_manifest_key: previous.map(|(key, _)| key),
+        };
+        let manifest_json = serde_json::to_vec(&manifest)?;
+        let manifest_key = format!(
+            "{}/manifest_{}.json",
+            self.manifests_prefix(),
+            timestamp.format("%Y%m%d_%H%M%S")
+        );
+
+        let upload_start = std::time::Instant::now();
+        let manifest_temp_file = tempfile::NamedTempFile::new()?;
+        tokio::fs::write(manifest_temp_file.path(), &manifest_json).await?;
+
+        let mut manifest_metadata = HashMap::from([
+            ("message_count".to_string(), message_count.to_string()),
+            ("encrypted".to_string(), self.config.encrypted.to_string()),
+        ]);
+        let encrypted_manifest_file = self.encrypt_for_upload(manifest_temp_file.path()).await?;
+        if let Some((_, encrypted_size)) = &encrypted_manifest_file {
+            manifest_metadata.insert("encrypted_size".to_string(), encrypted_size.to_string());
+        }
+        let manifest_upload_path = encrypted_manifest_file
+            .as_ref()
+            .map(|(f, _)| f.path())
+            .unwrap_or_else(|| manifest_temp_file.path());
+
+        self.backend
+            .put(&manifest_key, manifest_upload_path, manifest_metadata)
             .await?;
+        let upload_time = upload_start.elapsed();
 
-        // Read the compressed data
-        let compressed_data = response.body.collect().await?.into_bytes();
+        self.cleanup_old_backups().await?;
 
-        // Decompress
+        Ok(BackupMetrics {
+            original_size,
+            compressed_size,
+            compression_time_ms: compression_time.as_millis(),
+            upload_time_ms: upload_time.as_millis(),
+        })
+    }
+
+    async fn cleanup_incremental_backups(&self) -> Result<(), BackupError> {
+        let cutoff_date = Utc::now() - Duration::days(self.config.retention_days);
+
+        let mut manifest_objects = self.backend.list(&self.manifests_prefix()).await?;
+        manifest_objects.sort_by_key(|object| object.last_modified);
+
+        let (mut expired, mut retained): (Vec<_>, Vec<_>) = manifest_objects
+            .into_iter()
+            .partition(|object| object.last_modified < cutoff_date);
+
+        // Always keep the most recent manifest, even if stale, so a board
+        // that stops posting doesn't lose its only backup.
+        if retained.is_empty() {
+            if let Some(last) = expired.pop() {
+                retained.push(last);
+            }
+        }
+
+        let mut retained_hashes = HashSet::new();
+        for object in &retained {
+            let bytes = self
+                .decrypt_if_needed(&object.key, self.backend.get(&object.key).await?)
+                .await?;
+            let manifest: Manifest = serde_json::from_slice(&bytes)?;
+            retained_hashes.extend(manifest.chunk_hashes);
+        }
+
+        for object in &expired {
+            self.backend.delete(&object.key).await?;
+        }
+
+        let chunk_objects = self.backend.list(&self.chunks_prefix()).await?;
+        for object in chunk_objects {
+            let hash = object
+                .key
+                .rsplit('/')
+                .next()
+                .and_then(|name| name.strip_suffix(".zst"))
+                .unwrap_or_default();
+
+            if !retained_hashes.contains(hash) {
+                self.backend.delete(&object.key).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Restores the message set from a backup key, dispatching to the full
+    /// or incremental restore path depending on what kind of object the key
+    /// points at.
+    pub async fn restore_from_backup(&self, backup_key: &str) -> Result<Vec<Message>, BackupError> {
+        if backup_key.starts_with(&self.manifests_prefix()) {
+            self.restore_from_manifest(backup_key).await
+        } else {
+            self.restore_from_full_backup(backup_key).await
+        }
+    }
+
+    /// Restores the message set from a full backup, verifying the SHA-256
+    /// checksum `perform_full_backup` wrote into the object's metadata
+    /// against the decompressed bytes so silent corruption is caught rather
+    /// than silently restored. Backups written before the checksum existed
+    /// have nothing to compare against, so verification is skipped for them.
+    async fn restore_from_full_backup(&self, backup_key: &str) -> Result<Vec<Message>, BackupError> {
+        let stored_bytes = self.backend.get(backup_key).await?;
+        let compressed_data = self.decrypt_if_needed(backup_key, stored_bytes).await?;
         let decompressed = zstd::stream::decode_all(Cursor::new(compressed_data))?;
 
-        // Deserialize
+        if let Some(expected) = self.backend.head(backup_key).await?.get("checksum_sha256") {
+            let mut hasher = Sha256::new();
+            hasher.update(&decompressed);
+            let actual = format!("{:x}", hasher.finalize());
+            if &actual != expected {
+                return Err(BackupError::ChecksumMismatch {
+                    key: backup_key.to_string(),
+                    expected: expected.clone(),
+                    actual,
+                });
+            }
+        }
+
         let messages: Vec<Message> = serde_json::from_slice(&decompressed)?;
+        Ok(messages)
+    }
+
+    /// Restores the message set from an incremental manifest by resolving
+    /// each of its `chunk_hashes` to a chunk object and decoding it. A
+    /// manifest's `chunk_hashes` always covers every message present at
+    /// backup time (not just the ones that changed), so a single manifest is
+    /// sufficient to restore without walking `previous_manifest_key`. Each
+    /// decoded chunk is checked against the SHA-256 hash it is stored under.
+    async fn restore_from_manifest(&self, manifest_key: &str) -> Result<Vec<Message>, BackupError> {
+        let manifest_bytes = self
+            .decrypt_if_needed(manifest_key, self.backend.get(manifest_key).await?)
+            .await?;
+        let manifest: Manifest = serde_json::from_slice(&manifest_bytes)?;
+
+        let mut messages = Vec::with_capacity(manifest.chunk_hashes.len());
+        for hash in &manifest.chunk_hashes {
+            let chunk_key = format!("{}/{}.zst", self.chunks_prefix(), hash);
+            let stored_bytes = self.backend.get(&chunk_key).await?;
+            let compressed = self.decrypt_if_needed(&chunk_key, stored_bytes).await?;
+            let decompressed = zstd::stream::decode_all(Cursor::new(compressed))?;
+            let mut hasher = Sha256::new();
+            hasher.update(&decompressed);
+            let actual = format!("{:x}", hasher.finalize());
+            if &actual != hash {
+                return Err(BackupError::ChecksumMismatch {
+                    key: chunk_key,
+                    expected: hash.clone(),
+                    actual,
+                });
+            }
+            let message: Message = serde_json::from_slice(&decompressed)?;
+            messages.push(message);
+        }
 
         Ok(messages)
     }
+
+    /// Lists the restorable snapshot objects: full-backup blobs directly
+    /// under `config.prefix` in `Full` mode, or manifests under
+    /// `manifests_prefix()` in `Incremental` mode. Excludes the individual
+    /// content-addressed chunk objects, which aren't restorable on their own.
+    async fn list_restorable(&self) -> Result<Vec<BackupObject>, BackupError> {
+        match self.config.mode {
+            BackupMode::Full => {
+                let manifests_prefix = self.manifests_prefix();
+                let chunks_prefix = self.chunks_prefix();
+                let objects = self.backend.list(&self.config.prefix).await?;
+                Ok(objects
+                    .into_iter()
+                    .filter(|object| {
+                        !object.key.starts_with(&manifests_prefix)
+                            && !object.key.starts_with(&chunks_prefix)
+                    })
+                    .collect())
+            }
+            BackupMode::Incremental => self.backend.list(&self.manifests_prefix()).await,
+        }
+    }
+
+    /// Resolves the most recent restorable backup for the configured mode
+    /// and restores the message set from it. Used to repopulate the message
+    /// repo on startup.
+    pub async fn restore_latest_backup(&self) -> Result<Vec<Message>, BackupError> {
+        let mut objects = self.list_restorable().await?;
+        objects.sort_by_key(|object| object.last_modified);
+
+        let latest = objects
+            .pop()
+            .ok_or_else(|| BackupError::NotFound("no backups found".to_string()))?;
+
+        self.restore_from_backup(&latest.key).await
+    }
+}
+
+/// Summary of an available backup, as surfaced over the admin API.
+#[derive(serde::Serialize)]
+pub struct BackupSummary {
+    pub key: String,
+    pub last_modified: DateTime<Utc>,
+    pub message_count: Option<usize>,
+    pub original_size: Option<usize>,
+    pub compressed_size: Option<usize>,
+    pub encrypted: bool,
+}
+
+impl From<BackupObject> for BackupSummary {
+    fn from(object: BackupObject) -> Self {
+        let parse = |field: &str| object.metadata.get(field).and_then(|v| v.parse().ok());
+        Self {
+            key: object.key,
+            last_modified: object.last_modified,
+            message_count: parse("message_count"),
+            original_size: parse("original_size"),
+            compressed_size: parse("compressed_size"),
+            encrypted: object
+                .metadata
+                .get("encrypted")
+                .map(|v| v == "true")
+                .unwrap_or(false),
+        }
+    }
+}
+
+// Endpoint to list restorable backups (full backups, or manifests in
+// incremental mode) with the metadata recorded at backup time (message
+// count, sizes, encryption, timestamp).
+#[get("/api/backups")]
+pub async fn list_backups(
+    backup_system: Data<Arc<BackupSystem>>,
+) -> Result<HttpResponse, BackupError> {
+    let mut objects = backup_system.list_restorable().await?;
+    objects.sort_by_key(|object| object.last_modified);
+    objects.reverse();
+
+    let summaries: Vec<BackupSummary> = objects.into_iter().map(BackupSummary::from).collect();
+    Ok(HttpResponse::Ok().json(summaries))
+}
+
+// Endpoint to restore the live message set from a specific backup key,
+// verifying its checksum before the in-memory repo is replaced.
+#[post("/api/backups/{key:.*}/restore")]
+pub async fn restore_backup(
+    backup_system: Data<Arc<BackupSystem>>,
+    repo: Data<Arc<Mutex<Vec<Message>>>>,
+    key: WebPath<String>,
+) -> Result<HttpResponse, BackupError> {
+    if Path::new(key.as_str())
+        .components()
+        .any(|c| matches!(c, std::path::Component::ParentDir))
+    {
+        return Err(BackupError::InvalidKey(key.into_inner()));
+    }
+
+    let restored = backup_system.restore_from_backup(key.as_str()).await?;
+    let restored_count = restored.len();
+
+    let mut repo = repo.lock().map_err(|_| BackupError::LockPoisoned)?;
+    *repo = restored;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "restored_messages": restored_count })))
 }
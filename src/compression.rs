@@ -0,0 +1,208 @@
+use actix_web::{
+    body::{EitherBody, MessageBody},
+    dev::{ServiceRequest, ServiceResponse},
+    http::header,
+    Error, HttpResponse,
+};
+use futures_util::future::{ok, Ready};
+use futures_util::FutureExt;
+use std::{
+    env,
+    task::{Context, Poll},
+};
+
+const DEFAULT_MIN_BYTES: usize = 256;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Encoding {
+    Brotli,
+    Gzip,
+}
+
+impl Encoding {
+    fn as_str(self) -> &'static str {
+        match self {
+            Encoding::Brotli => "br",
+            Encoding::Gzip => "gzip",
+        }
+    }
+}
+
+/// Picks the best encoding the client advertised, preferring `br` over
+/// `gzip` over sending the body uncompressed.
+fn negotiate(accept_encoding: &str) -> Option<Encoding> {
+    let offered: Vec<&str> = accept_encoding
+        .split(',')
+        .map(|s| s.split(';').next().unwrap_or("").trim())
+        .collect();
+
+    if offered.iter().any(|e| e.eq_ignore_ascii_case("br")) {
+        Some(Encoding::Brotli)
+    } else if offered.iter().any(|e| e.eq_ignore_ascii_case("gzip")) {
+        Some(Encoding::Gzip)
+    } else {
+        None
+    }
+}
+
+fn compress(encoding: Encoding, data: &[u8]) -> std::io::Result<Vec<u8>> {
+    use std::io::Write;
+
+    match encoding {
+        Encoding::Gzip => {
+            let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(data)?;
+            encoder.finish()
+        }
+        Encoding::Brotli => {
+            let mut out = Vec::new();
+            {
+                let mut writer = brotli::CompressorWriter::new(&mut out, 4096, 5, 22);
+                writer.write_all(data)?;
+            }
+            Ok(out)
+        }
+    }
+}
+
+/// Negotiates `br`/`gzip` compression for responses based on the request's
+/// `Accept-Encoding` header. Skips bodies that are already encoded, already
+/// in a compressed image format, or smaller than `min_bytes`.
+pub struct Compression {
+    min_bytes: usize,
+}
+
+impl Compression {
+    pub fn new(min_bytes: usize) -> Self {
+        Self { min_bytes }
+    }
+
+    /// Reads `COMPRESSION_MIN_BYTES` at startup, falling back to 256 bytes.
+    pub fn from_env() -> Self {
+        let min_bytes = env::var("COMPRESSION_MIN_BYTES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_MIN_BYTES);
+        Self::new(min_bytes)
+    }
+}
+
+impl<S, B> actix_service::Transform<S, ServiceRequest> for Compression
+where
+    S: actix_service::Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = CompressionMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(CompressionMiddleware {
+            service,
+            min_bytes: self.min_bytes,
+        })
+    }
+}
+
+pub struct CompressionMiddleware<S> {
+    service: S,
+    min_bytes: usize,
+}
+
+impl<S, B> actix_service::Service<ServiceRequest> for CompressionMiddleware<S>
+where
+    S: actix_service::Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = futures_util::future::LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let encoding = req
+            .headers()
+            .get(header::ACCEPT_ENCODING)
+            .and_then(|v| v.to_str().ok())
+            .and_then(negotiate);
+        let min_bytes = self.min_bytes;
+        let fut = self.service.call(req);
+
+        async move {
+            let res = fut.await?;
+
+            let Some(encoding) = encoding else {
+                return Ok(res.map_into_left_body());
+            };
+
+            if res.headers().contains_key(header::CONTENT_ENCODING) {
+                return Ok(res.map_into_left_body());
+            }
+
+            // A 101 response (e.g. the websocket feed's upgrade handshake)
+            // has no body to buffer and is followed by an open-ended stream;
+            // buffering it would hang the connection instead of upgrading.
+            let is_upgrade = res.status() == actix_web::http::StatusCode::SWITCHING_PROTOCOLS
+                || res
+                    .headers()
+                    .get(header::CONNECTION)
+                    .and_then(|v| v.to_str().ok())
+                    .map(|v| v.eq_ignore_ascii_case("upgrade"))
+                    .unwrap_or(false);
+            if is_upgrade {
+                return Ok(res.map_into_left_body());
+            }
+
+            let is_image = res
+                .headers()
+                .get(header::CONTENT_TYPE)
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.starts_with("image/"))
+                .unwrap_or(false);
+            if is_image {
+                return Ok(res.map_into_left_body());
+            }
+
+            let (http_req, http_response) = res.into_parts();
+            let status = http_response.status();
+            let headers = http_response.headers().clone();
+            let body = http_response.into_body();
+
+            let bytes = actix_web::body::to_bytes(body).await.map_err(|_| {
+                actix_web::error::ErrorInternalServerError("Failed to buffer response body")
+            })?;
+
+            if bytes.len() < min_bytes {
+                let mut builder = HttpResponse::build(status);
+                for (name, value) in headers.iter() {
+                    builder.insert_header((name.clone(), value.clone()));
+                }
+                let response = builder.body(bytes);
+                return Ok(ServiceResponse::new(http_req, response).map_into_right_body());
+            }
+
+            let compressed = compress(encoding, &bytes).map_err(actix_web::error::ErrorInternalServerError)?;
+
+            let mut builder = HttpResponse::build(status);
+            for (name, value) in headers.iter() {
+                if name == header::CONTENT_LENGTH {
+                    continue;
+                }
+                builder.insert_header((name.clone(), value.clone()));
+            }
+            builder.insert_header((header::CONTENT_ENCODING, encoding.as_str()));
+            builder.insert_header((header::VARY, "Accept-Encoding"));
+            let response = builder.body(compressed);
+
+            Ok(ServiceResponse::new(http_req, response).map_into_right_body())
+        }
+        .boxed_local()
+    }
+}
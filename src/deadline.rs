@@ -0,0 +1,132 @@
+use actix_web::{
+    body::BoxBody,
+    dev::{ServiceRequest, ServiceResponse},
+    http::StatusCode,
+    Error, HttpResponse,
+};
+use futures_util::future::{ok, Ready};
+use std::{
+    env,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+const DEFAULT_TIMEOUT_SECS: u64 = 10;
+
+/// Bounds how long a wrapped route may run before the connection is given
+/// back a `408 Request Timeout` instead of hanging on a slow handler (e.g. a
+/// contended `Mutex` or a stalled DynamoDB call).
+pub struct Deadline {
+    duration: Duration,
+}
+
+impl Deadline {
+    pub fn new(duration: Duration) -> Self {
+        Self { duration }
+    }
+
+    /// Reads `REQUEST_TIMEOUT_SECS` at startup, falling back to 10 seconds.
+    /// A duration of zero disables the deadline for routes that opt out.
+    pub fn from_env() -> Self {
+        let secs = env::var("REQUEST_TIMEOUT_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_TIMEOUT_SECS);
+        Self::new(Duration::from_secs(secs))
+    }
+}
+
+impl<S> actix_service::Transform<S, ServiceRequest> for Deadline
+where
+    S: actix_service::Service<ServiceRequest, Response = ServiceResponse<BoxBody>, Error = Error>
+        + 'static,
+    S::Future: 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = DeadlineMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(DeadlineMiddleware {
+            service,
+            duration: self.duration,
+        })
+    }
+}
+
+pub struct DeadlineMiddleware<S> {
+    service: S,
+    duration: Duration,
+}
+
+type InnerFuture = Pin<Box<dyn Future<Output = Result<ServiceResponse<BoxBody>, Error>>>>;
+
+impl<S> actix_service::Service<ServiceRequest> for DeadlineMiddleware<S>
+where
+    S: actix_service::Service<ServiceRequest, Response = ServiceResponse<BoxBody>, Error = Error>
+        + 'static,
+    S::Future: 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type Future = DeadlineFuture;
+
+    fn poll_ready(&self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        if self.duration.is_zero() {
+            return DeadlineFuture::Untimed {
+                future: Box::pin(self.service.call(req)),
+            };
+        }
+
+        let http_req = req.request().clone();
+        let future: InnerFuture = Box::pin(self.service.call(req));
+        DeadlineFuture::Timed {
+            timeout: Box::pin(actix_rt::time::timeout(self.duration, future)),
+            req: Some(http_req),
+        }
+    }
+}
+
+/// Drives a wrapped request. `Timed` races the inner future against the
+/// deadline; `Untimed` polls it straight through so individual routes can
+/// opt out by constructing a `Deadline` with a zero duration.
+pub enum DeadlineFuture {
+    Timed {
+        timeout: Pin<Box<actix_rt::time::Timeout<InnerFuture>>>,
+        req: Option<actix_web::HttpRequest>,
+    },
+    Untimed {
+        future: InnerFuture,
+    },
+}
+
+impl Future for DeadlineFuture {
+    type Output = Result<ServiceResponse<BoxBody>, Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.get_mut() {
+            DeadlineFuture::Untimed { future } => future.as_mut().poll(cx),
+            DeadlineFuture::Timed { timeout, req } => match timeout.as_mut().poll(cx) {
+                Poll::Ready(Ok(result)) => Poll::Ready(result),
+                Poll::Ready(Err(_elapsed)) => {
+                    let http_req = req.take().expect("DeadlineFuture polled after completion");
+                    let response = HttpResponse::build(StatusCode::REQUEST_TIMEOUT)
+                        .json(serde_json::json!({ "error": "request_timeout" }));
+                    Poll::Ready(Ok(ServiceResponse::new(
+                        http_req,
+                        response.map_into_boxed_body(),
+                    )))
+                }
+                Poll::Pending => Poll::Pending,
+            },
+        }
+    }
+}
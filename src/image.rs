@@ -0,0 +1,157 @@
+use actix_multipart::Multipart;
+use actix_web::{
+    get,
+    http::StatusCode,
+    post,
+    web::{Data, Path},
+    HttpResponse, ResponseError,
+};
+use futures_util::StreamExt as _;
+use std::sync::{Arc, Mutex};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::messages::Message;
+
+const MAX_IMAGE_BYTES: usize = 5 * 1024 * 1024;
+const ALLOWED_MIME_TYPES: &[&str] = &["image/jpeg", "image/png", "image/webp"];
+
+#[derive(Debug, Error)]
+pub enum ImageError {
+    #[error("message not found")]
+    NotFound,
+
+    #[error("no image attached to this message")]
+    NoImage,
+
+    #[error("image exceeds the {0} byte limit")]
+    TooLarge(usize),
+
+    #[error("unsupported image type: {0}")]
+    UnsupportedType(String),
+
+    #[error("image content does not match declared type {0}")]
+    ContentMismatch(String),
+
+    #[error("missing image part in upload")]
+    MissingImagePart,
+
+    #[error("multipart error: {0}")]
+    Multipart(#[from] actix_multipart::MultipartError),
+
+    #[error("failed to acquire lock on message repo")]
+    LockPoisoned,
+}
+
+impl ResponseError for ImageError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ImageError::NotFound | ImageError::NoImage => StatusCode::NOT_FOUND,
+            ImageError::TooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            ImageError::UnsupportedType(_) | ImageError::ContentMismatch(_) => {
+                StatusCode::UNSUPPORTED_MEDIA_TYPE
+            }
+            ImageError::MissingImagePart | ImageError::Multipart(_) => StatusCode::BAD_REQUEST,
+            ImageError::LockPoisoned => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code())
+            .json(serde_json::json!({ "error": self.to_string() }))
+    }
+}
+
+/// Identifies an image's real format from its leading magic bytes. Returns
+/// `None` for anything that doesn't match a known signature, so a client
+/// can't get an arbitrary payload accepted by lying in the multipart
+/// `Content-Type` header.
+fn sniff_image_mime_type(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("image/jpeg")
+    } else if bytes.starts_with(&[0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A]) {
+        Some("image/png")
+    } else if bytes.len() >= 12 && &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        Some("image/webp")
+    } else {
+        None
+    }
+}
+
+// Endpoint to upload an image for an existing message. Streams the upload
+// into memory, enforcing a size cap and a MIME allowlist before storing it.
+#[post("/api/messages/{id}/image")]
+pub async fn upload_image(
+    repo: Data<Arc<Mutex<Vec<Message>>>>,
+    id: Path<Uuid>,
+    mut payload: Multipart,
+) -> Result<HttpResponse, ImageError> {
+    let mut image_data: Option<Vec<u8>> = None;
+    let mut image_mime_type: Option<String> = None;
+
+    while let Some(field) = payload.next().await {
+        let mut field = field?;
+
+        let content_type = field
+            .content_type()
+            .map(|m| m.essence_str().to_string())
+            .unwrap_or_default();
+
+        if !ALLOWED_MIME_TYPES.contains(&content_type.as_str()) {
+            return Err(ImageError::UnsupportedType(content_type));
+        }
+
+        let mut bytes = Vec::new();
+        while let Some(chunk) = field.next().await {
+            let chunk = chunk?;
+            if bytes.len() + chunk.len() > MAX_IMAGE_BYTES {
+                return Err(ImageError::TooLarge(MAX_IMAGE_BYTES));
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+
+        if sniff_image_mime_type(&bytes) != Some(content_type.as_str()) {
+            return Err(ImageError::ContentMismatch(content_type));
+        }
+
+        image_mime_type = Some(content_type);
+        image_data = Some(bytes);
+        break;
+    }
+
+    let (image_data, image_mime_type) = match (image_data, image_mime_type) {
+        (Some(data), Some(mime_type)) => (data, mime_type),
+        _ => return Err(ImageError::MissingImagePart),
+    };
+
+    let mut repo = repo.lock().map_err(|_| ImageError::LockPoisoned)?;
+    let message = repo
+        .iter_mut()
+        .find(|m| m.id == *id)
+        .ok_or(ImageError::NotFound)?;
+
+    message.image_data = Some(image_data);
+    message.image_mime_type = Some(image_mime_type);
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+// Endpoint to fetch the stored image bytes for a message
+#[get("/api/messages/{id}/image")]
+pub async fn get_image(
+    repo: Data<Arc<Mutex<Vec<Message>>>>,
+    id: Path<Uuid>,
+) -> Result<HttpResponse, ImageError> {
+    let repo = repo.lock().map_err(|_| ImageError::LockPoisoned)?;
+    let message = repo
+        .iter()
+        .find(|m| m.id == *id)
+        .ok_or(ImageError::NotFound)?;
+
+    match (&message.image_data, &message.image_mime_type) {
+        (Some(data), Some(mime_type)) => Ok(HttpResponse::Ok()
+            .content_type(mime_type.as_str())
+            .body(data.clone())),
+        _ => Err(ImageError::NoImage),
+    }
+}
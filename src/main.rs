@@ -11,19 +11,29 @@ use std::{
     io::BufReader,
     sync::{Arc, Mutex},
 };
+use tokio::sync::broadcast;
 
 mod auth;
 mod backup;
+mod compression;
+mod deadline;
+mod image;
 mod langs;
 mod messages;
 mod routing;
 mod security_headers;
 mod tests;
+mod ws;
 
 use backup::{BackupConfig, BackupSystem};
+use compression::Compression;
 use messages::{remove_old_messages, Message};
 use security_headers::SecurityHeaders;
 
+/// How many unconsumed messages a websocket feed subscriber may lag behind
+/// before it starts missing broadcasts (and gets disconnected as a result).
+const FEED_CHANNEL_CAPACITY: usize = 256;
+
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     dotenv().expect("Failed to read .env file");
@@ -41,9 +51,17 @@ async fn main() -> std::io::Result<()> {
     let tls_messages_clone = messages.clone();
     let backup_messages_clone = messages.clone();
 
-    if let Err(e) = configure_backup_system(backup_messages_clone.clone()).await {
-        log::error!("Failed to configure backup system: {}", e);
-    }
+    let (feed_sender, _) = broadcast::channel::<Message>(FEED_CHANNEL_CAPACITY);
+    let insecure_feed = feed_sender.clone();
+    let secure_feed = feed_sender.clone();
+
+    let backup_system = match configure_backup_system(backup_messages_clone.clone()).await {
+        Ok(backup_system) => Some(backup_system),
+        Err(e) => {
+            log::error!("Failed to configure backup system: {}", e);
+            None
+        }
+    };
 
     tokio::spawn(async move {
         let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(60));
@@ -68,23 +86,32 @@ async fn main() -> std::io::Result<()> {
         App::new()
             .wrap(logger)
             .wrap(SecurityHeaders)
+            .wrap(Compression::from_env())
             .wrap(RateLimiter::new(Arc::clone(&limiter)))
             .app_data(Data::new(messages.clone()))
+            .app_data(Data::new(insecure_feed.clone()))
             .configure(routing::configure_insecure_message_routes)
     };
 
     let secure_app_factory = move || {
         let logger = Logger::default();
-        App::new()
+        let mut app = App::new()
             .wrap(logger)
             .wrap(SecurityHeaders)
+            .wrap(Compression::from_env())
             .wrap(RateLimiter::new(Arc::clone(&tls_limiter)))
             .app_data(Data::new(tls_messages_clone.clone()))
-            .service(
-                scope("/admin")
-                    .wrap(ApiKeyMiddleware)
-                    .configure(routing::configure_secure_message_routes),
-            )
+            .app_data(Data::new(secure_feed.clone()));
+
+        if let Some(backup_system) = &backup_system {
+            app = app.app_data(Data::new(backup_system.clone()));
+        }
+
+        app.service(
+            scope("/admin")
+                .wrap(ApiKeyMiddleware::new())
+                .configure(routing::configure_secure_message_routes),
+        )
     };
 
     let http_server = HttpServer::new(insecure_app_factory.clone())
@@ -133,9 +160,9 @@ fn load_rustls_config(cert_path: &str, key_path: &str) -> std::io::Result<Server
 
 async fn configure_backup_system(
     messages: Arc<Mutex<Vec<Message>>>,
-) -> Result<(), Box<dyn std::error::Error>> {
+) -> Result<Arc<BackupSystem>, Box<dyn std::error::Error>> {
     let config = BackupConfig::from_env()?;
-    let backup_system = BackupSystem::new(messages.clone(), config).await?;
+    let backup_system = Arc::new(BackupSystem::new(messages.clone(), config).await?);
 
     {
         let mut messages_guard = messages.lock().unwrap();
@@ -152,7 +179,7 @@ async fn configure_backup_system(
         }
     }
 
-    backup_system.start_backup_task().await;
+    backup_system.clone().start_backup_task().await;
 
-    Ok(())
+    Ok(backup_system)
 }
@@ -1,10 +1,14 @@
 use actix_web::{
-    delete, get, patch, post,
+    delete,
+    http::header,
+    get, patch, post,
     web::{Data, Json, Path},
-    HttpResponse,
+    HttpRequest, HttpResponse,
 };
-use chrono::{DateTime, Duration, Utc};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use std::hash::{Hash, Hasher};
 use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast;
 use uuid::Uuid;
 
 use crate::langs::Langs;
@@ -26,6 +30,43 @@ pub struct Message {
     pub expires: Expiration,
     pub title: String,
     pub image_url : Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image_data: Option<Vec<u8>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image_mime_type: Option<String>,
+}
+
+/// The client-facing view of a `Message`, omitting the raw image bytes:
+/// those are only ever served through the dedicated
+/// `GET /api/messages/{id}/image` endpoint, so inlining them into every
+/// listing/feed response would ship up to 5 MiB per message on every poll.
+/// `Message` itself keeps the full image payload serializable because
+/// backup/restore (`src/backup.rs`) persists it directly.
+#[derive(serde::Serialize, Clone, Debug, PartialEq)]
+pub struct MessageSummary {
+    pub id: Uuid,
+    pub created: DateTime<Utc>,
+    pub content: String,
+    pub lang: Langs,
+    pub expires: Expiration,
+    pub title: String,
+    pub image_url: Option<String>,
+    pub has_image: bool,
+}
+
+impl From<&Message> for MessageSummary {
+    fn from(message: &Message) -> Self {
+        Self {
+            id: message.id,
+            created: message.created,
+            content: message.content.clone(),
+            lang: message.lang.clone(),
+            expires: message.expires,
+            title: message.title.clone(),
+            image_url: message.image_url.clone(),
+            has_image: message.image_data.is_some(),
+        }
+    }
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
@@ -45,16 +86,14 @@ pub struct EditMessage {
     pub image_url : Option<String>,
 }
 
-// Endpoint to post a new message to the shared message repo
+// Endpoint to post a new message to the shared message repo. Fans the new
+// message out to any connected websocket feed subscribers.
 #[post("/api/messages")]
 pub async fn add_message(
     repo: Data<Arc<Mutex<Vec<Message>>>>,
+    feed: Data<broadcast::Sender<Message>>,
     body: Json<NewMessage>,
 ) -> Result<HttpResponse, actix_web::Error> {
-    let mut repo = repo.lock().map_err(|_| {
-        actix_web::error::ErrorInternalServerError("Failed to acquire lock on message repo")
-    })?;
-
     let new_message = Message {
         id: Uuid::new_v4(),
         created: Utc::now(),
@@ -63,24 +102,101 @@ pub async fn add_message(
         expires: body.expires.clone(),
         title: body.title.clone(),
         image_url: body.image_url.clone(),
+        image_data: None,
+        image_mime_type: None,
     };
-    repo.push(new_message);
+
+    {
+        let mut repo = repo.lock().map_err(|_| {
+            actix_web::error::ErrorInternalServerError("Failed to acquire lock on message repo")
+        })?;
+        repo.push(new_message.clone());
+    }
+
+    // A send error just means nobody is currently subscribed; that's fine.
+    let _ = feed.send(new_message);
+
     Ok(HttpResponse::Ok().finish())
 }
 
-// Endpoint to get a message by language
+// Endpoint to get a message by language. Supports conditional requests via
+// `ETag`/`If-None-Match` and `Last-Modified`/`If-Modified-Since` so clients
+// that poll frequently can avoid re-downloading an unchanged message set.
 #[get("/api/messages/{lang}")]
 pub async fn get_messages_by_lang(
+    req: HttpRequest,
     repo: Data<Arc<Mutex<Vec<Message>>>>,
     lang: Path<Langs>,
 ) -> Result<HttpResponse, actix_web::Error> {
-    let repo = repo.lock().map_err(|_| {
-        actix_web::error::ErrorInternalServerError("Failed to acquire lock on message repo")
-    })?;
-    let messages: Vec<Message> = repo.iter().filter(|x| x.lang == *lang).cloned().collect();
-    Ok(HttpResponse::Ok()
+    let messages: Vec<Message> = {
+        let repo = repo.lock().map_err(|_| {
+            actix_web::error::ErrorInternalServerError("Failed to acquire lock on message repo")
+        })?;
+        repo.iter().filter(|x| x.lang == *lang).cloned().collect()
+    };
+
+    let summaries: Vec<MessageSummary> = messages.iter().map(MessageSummary::from).collect();
+    let body = serde_json::to_string(&summaries)
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let etag = format!("\"{:x}\"", hash_body(&body));
+    let last_modified = messages.iter().map(|m| m.created).max();
+
+    let not_modified = if let Some(if_none_match) = req.headers().get(header::IF_NONE_MATCH) {
+        if_none_match
+            .to_str()
+            .map(|v| v.trim() == etag)
+            .unwrap_or(false)
+    } else if let Some(since) = req
+        .headers()
+        .get(header::IF_MODIFIED_SINCE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(parse_http_date)
+    {
+        last_modified
+            .map(|lm| truncate_to_secs(lm) <= truncate_to_secs(since))
+            .unwrap_or(false)
+    } else {
+        false
+    };
+
+    if not_modified {
+        return Ok(conditional_response(HttpResponse::NotModified(), &etag, last_modified).finish());
+    }
+
+    let mut response = conditional_response(HttpResponse::Ok(), &etag, last_modified);
+    Ok(response
         .content_type("application/json; charset=utf-8")
-        .json(messages))
+        .body(body))
+}
+
+fn conditional_response(
+    mut builder: actix_web::HttpResponseBuilder,
+    etag: &str,
+    last_modified: Option<DateTime<Utc>>,
+) -> actix_web::HttpResponseBuilder {
+    builder.insert_header((header::ETAG, etag.to_string()));
+    if let Some(last_modified) = last_modified {
+        builder.insert_header((header::LAST_MODIFIED, format_http_date(last_modified)));
+    }
+    builder
+}
+
+fn hash_body(body: &str) -> u64 {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    body.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn format_http_date(dt: DateTime<Utc>) -> String {
+    dt.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+fn parse_http_date(s: &str) -> Option<DateTime<Utc>> {
+    Utc.datetime_from_str(s, "%a, %d %b %Y %H:%M:%S GMT").ok()
+}
+
+fn truncate_to_secs(dt: DateTime<Utc>) -> DateTime<Utc> {
+    Utc.timestamp_opt(dt.timestamp(), 0).single().unwrap_or(dt)
 }
 
 // Endpoint to edit a message
@@ -1,12 +1,29 @@
-use actix_web::web::ServiceConfig;
+use actix_web::web::{scope, ServiceConfig};
+use crate::backup::{list_backups, restore_backup};
+use crate::deadline::Deadline;
+use crate::image::{get_image, upload_image};
 use crate::messages::*;
+use crate::ws::message_feed;
 
 pub fn configure_secure_message_routes(cfg: &mut ServiceConfig) {
-    cfg.service(add_message);
-    cfg.service(edit_message);
-    cfg.service(delete_message);
+    cfg.service(
+        scope("")
+            .wrap(Deadline::from_env())
+            .service(add_message)
+            .service(edit_message)
+            .service(delete_message)
+            .service(upload_image)
+            .service(list_backups)
+            .service(restore_backup),
+    );
 }
 
 pub fn configure_insecure_message_routes(cfg: &mut ServiceConfig) {
-    cfg.service(get_messages_by_lang);
+    cfg.service(
+        scope("")
+            .wrap(Deadline::from_env())
+            .service(message_feed)
+            .service(get_messages_by_lang)
+            .service(get_image),
+    );
 }
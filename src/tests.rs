@@ -1,22 +1,33 @@
 #[cfg(test)]
 
 mod tests {
-    use actix_web::{http::StatusCode, test, web::Data, App};
+    use actix_web::{http::header, http::StatusCode, test, web, web::Data, App, HttpResponse};
     use chrono::Utc;
+    use sha2::{Digest, Sha256};
     use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use tokio::sync::broadcast;
     use uuid::Uuid;
 
+    use crate::auth::{keys_match, ApiKeyMiddleware};
+    use crate::compression::Compression;
+    use crate::deadline::Deadline;
+    use crate::backup::{decrypt_blob, encrypt_blob, list_backups, restore_backup, BackupConfig, BackupSystem};
+    use crate::image::{get_image, upload_image};
     use crate::langs::Langs;
     use crate::messages::*;
+    use crate::ws::message_feed;
 
 
 
     #[actix_rt::test]
     async fn test_add_message() {
         let messages: Arc<Mutex<Vec<Message>>> = Arc::new(Mutex::new(Vec::new()));
+        let (feed, _) = broadcast::channel::<Message>(16);
         let mut app = test::init_service(
             App::new()
                 .app_data(Data::new(messages.clone()))
+                .app_data(Data::new(feed))
                 .service(add_message),
         )
         .await;
@@ -75,8 +86,67 @@ mod tests {
         let resp = test::call_service(&mut app, req).await;
         assert_eq!(resp.status(), StatusCode::OK);
 
-        let returned_messages: Vec<Message> = test::read_body_json(resp).await;
-        assert!(returned_messages.contains(&message));
+        let returned_messages: Vec<MessageSummary> = test::read_body_json(resp).await;
+        assert!(returned_messages.contains(&MessageSummary::from(&message)));
+    }
+
+    #[actix_rt::test]
+    async fn test_get_messages_by_lang_returns_not_modified_for_matching_etag() {
+        let message = Message {
+            id: Uuid::new_v4(),
+            created: Utc::now(),
+            content: "Hello, world!".to_string(),
+            lang: Langs::English,
+            expires: Expiration::Week,
+            title: "Test".to_string(),
+            image_url: None,
+            image_data: None,
+            image_mime_type: None,
+        };
+        let messages: Arc<Mutex<Vec<Message>>> = Arc::new(Mutex::new(vec![message.clone()]));
+        let mut app = test::init_service(
+            App::new()
+                .app_data(Data::new(messages.clone()))
+                .service(get_messages_by_lang),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri("/api/messages/English")
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let etag = resp
+            .headers()
+            .get(header::ETAG)
+            .expect("response should carry an ETag")
+            .to_str()
+            .unwrap()
+            .to_string();
+        assert!(resp.headers().contains_key(header::LAST_MODIFIED));
+
+        let req = test::TestRequest::get()
+            .uri("/api/messages/English")
+            .insert_header((header::IF_NONE_MATCH, etag.clone()))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(
+            resp.headers()
+                .get(header::ETAG)
+                .expect("304 should still carry the ETag")
+                .to_str()
+                .unwrap(),
+            etag
+        );
+
+        // A stale If-None-Match must not suppress the body.
+        let req = test::TestRequest::get()
+            .uri("/api/messages/English")
+            .insert_header((header::IF_NONE_MATCH, "\"stale-etag\""))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
     }
 
     #[actix_rt::test]
@@ -155,4 +225,604 @@ mod tests {
         let messages = messages.lock().unwrap();
         assert!(messages.iter().find(|x| x.id == message.id).is_none());
     }
+
+    #[actix_rt::test]
+    async fn test_upload_image_and_get_image() {
+        let message = Message {
+            id: Uuid::new_v4(),
+            created: Utc::now(),
+            content: "Hello, world!".to_string(),
+            lang: Langs::English,
+            expires: Expiration::Week,
+            title: "Test".to_string(),
+            image_url: None,
+            image_data: None,
+            image_mime_type: None,
+        };
+        let messages: Arc<Mutex<Vec<Message>>> = Arc::new(Mutex::new(vec![message.clone()]));
+        let mut app = test::init_service(
+            App::new()
+                .app_data(Data::new(messages.clone()))
+                .service(upload_image)
+                .service(get_image),
+        )
+        .await;
+
+        let boundary = "X-BOUNDARY";
+        let png_magic: &[u8] = &[0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A];
+        let image_bytes = [png_magic, b"fake-png-bytes"].concat();
+        let mut body = Vec::new();
+        body.extend_from_slice(
+            format!("--{boundary}\r\nContent-Disposition: form-data; name=\"image\"; filename=\"pixel.png\"\r\nContent-Type: image/png\r\n\r\n").as_bytes(),
+        );
+        body.extend_from_slice(&image_bytes);
+        body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/api/messages/{}/image", message.id))
+            .insert_header((
+                header::CONTENT_TYPE,
+                format!("multipart/form-data; boundary={}", boundary),
+            ))
+            .set_payload(body)
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/api/messages/{}/image", message.id))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers().get(header::CONTENT_TYPE).unwrap(),
+            "image/png"
+        );
+
+        let body = test::read_body(resp).await;
+        assert_eq!(body, image_bytes.as_slice());
+    }
+
+    #[actix_rt::test]
+    async fn test_upload_image_rejects_content_mismatch() {
+        let message = Message {
+            id: Uuid::new_v4(),
+            created: Utc::now(),
+            content: "Hello, world!".to_string(),
+            lang: Langs::English,
+            expires: Expiration::Week,
+            title: "Test".to_string(),
+            image_url: None,
+            image_data: None,
+            image_mime_type: None,
+        };
+        let messages: Arc<Mutex<Vec<Message>>> = Arc::new(Mutex::new(vec![message.clone()]));
+        let mut app = test::init_service(
+            App::new()
+                .app_data(Data::new(messages.clone()))
+                .service(upload_image),
+        )
+        .await;
+
+        let boundary = "X-BOUNDARY";
+        let body = format!(
+            "--{boundary}\r\nContent-Disposition: form-data; name=\"image\"; filename=\"pixel.png\"\r\nContent-Type: image/png\r\n\r\n<script>alert(1)</script>\r\n--{boundary}--\r\n",
+        );
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/api/messages/{}/image", message.id))
+            .insert_header((
+                header::CONTENT_TYPE,
+                format!("multipart/form-data; boundary={}", boundary),
+            ))
+            .set_payload(body)
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    }
+
+    #[actix_rt::test]
+    async fn test_get_image_without_upload_returns_not_found() {
+        let message = Message {
+            id: Uuid::new_v4(),
+            created: Utc::now(),
+            content: "Hello, world!".to_string(),
+            lang: Langs::English,
+            expires: Expiration::Week,
+            title: "Test".to_string(),
+            image_url: None,
+            image_data: None,
+            image_mime_type: None,
+        };
+        let messages: Arc<Mutex<Vec<Message>>> = Arc::new(Mutex::new(vec![message.clone()]));
+        let mut app = test::init_service(
+            App::new()
+                .app_data(Data::new(messages.clone()))
+                .service(get_image),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/api/messages/{}/image", message.id))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[actix_rt::test]
+    async fn test_message_feed_upgrades_to_websocket() {
+        let messages: Arc<Mutex<Vec<Message>>> = Arc::new(Mutex::new(Vec::new()));
+        let (feed, _) = broadcast::channel::<Message>(16);
+        let mut app = test::init_service(
+            App::new()
+                .app_data(Data::new(messages.clone()))
+                .app_data(Data::new(feed))
+                .service(message_feed),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri("/api/messages/feed")
+            .insert_header((header::CONNECTION, "Upgrade"))
+            .insert_header((header::UPGRADE, "websocket"))
+            .insert_header(("Sec-WebSocket-Version", "13"))
+            .insert_header(("Sec-WebSocket-Key", "dGhlIHNhbXBsZSBub25jZQ=="))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::SWITCHING_PROTOCOLS);
+    }
+
+    // Seeds a filesystem-backed BackupSystem with a single pre-existing full
+    // backup, bypassing the private `perform_backup` path so the test only
+    // exercises the public `list_backups`/`restore_backup` handlers.
+    #[actix_rt::test]
+    async fn test_list_backups_and_restore_backup() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        std::env::set_var("AWS_BACKUP_BUCKET", "test-bucket");
+        std::env::set_var("AWS_REGION", "us-east-1");
+        std::env::set_var("BACKUP_BACKEND", "filesystem");
+        std::env::set_var("BACKUP_LOCAL_PATH", tmp_dir.path().to_str().unwrap());
+        std::env::remove_var("AWS_BACKUP_PREFIX");
+        std::env::remove_var("BACKUP_MODE");
+        std::env::remove_var("BACKUP_ENCRYPTION_KEY");
+
+        let config = BackupConfig::from_env().unwrap();
+        let prefix = config.prefix.clone();
+        let messages: Arc<Mutex<Vec<Message>>> = Arc::new(Mutex::new(Vec::new()));
+        let backup_system = Arc::new(BackupSystem::new(messages.clone(), config).await.unwrap());
+
+        let backed_up = vec![Message {
+            id: Uuid::new_v4(),
+            created: Utc::now(),
+            content: "Backed up".to_string(),
+            lang: Langs::English,
+            expires: Expiration::Week,
+            title: "Test".to_string(),
+            image_url: None,
+            image_data: None,
+            image_mime_type: None,
+        }];
+        let json = serde_json::to_vec(&backed_up).unwrap();
+        let compressed = zstd::stream::encode_all(std::io::Cursor::new(json.as_slice()), 3).unwrap();
+
+        let backup_key = format!("{}/backup_20260101_000000.json.zst", prefix);
+        let backup_path = tmp_dir.path().join(&backup_key);
+        std::fs::create_dir_all(backup_path.parent().unwrap()).unwrap();
+        std::fs::write(&backup_path, &compressed).unwrap();
+        std::fs::write(
+            format!("{}.meta.json", backup_path.display()),
+            serde_json::to_vec(&std::collections::HashMap::from([
+                ("message_count".to_string(), "1".to_string()),
+                ("encrypted".to_string(), "false".to_string()),
+            ]))
+            .unwrap(),
+        )
+        .unwrap();
+
+        let mut app = test::init_service(
+            App::new()
+                .app_data(Data::new(backup_system.clone()))
+                .app_data(Data::new(messages.clone()))
+                .service(list_backups)
+                .service(restore_backup),
+        )
+        .await;
+
+        let req = test::TestRequest::get().uri("/api/backups").to_request();
+        let resp = test::call_service(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let summaries: Vec<serde_json::Value> = test::read_body_json(resp).await;
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0]["key"], backup_key);
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/api/backups/{}/restore", backup_key))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let messages = messages.lock().unwrap();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].content, "Backed up");
+    }
+
+    #[actix_rt::test]
+    async fn test_restore_backup_rejects_path_traversal() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        std::env::set_var("AWS_BACKUP_BUCKET", "test-bucket");
+        std::env::set_var("AWS_REGION", "us-east-1");
+        std::env::set_var("BACKUP_BACKEND", "filesystem");
+        std::env::set_var("BACKUP_LOCAL_PATH", tmp_dir.path().to_str().unwrap());
+        std::env::remove_var("AWS_BACKUP_PREFIX");
+        std::env::remove_var("BACKUP_MODE");
+        std::env::remove_var("BACKUP_ENCRYPTION_KEY");
+
+        let config = BackupConfig::from_env().unwrap();
+        let messages: Arc<Mutex<Vec<Message>>> = Arc::new(Mutex::new(Vec::new()));
+        let backup_system = Arc::new(BackupSystem::new(messages.clone(), config).await.unwrap());
+
+        let mut app = test::init_service(
+            App::new()
+                .app_data(Data::new(backup_system.clone()))
+                .app_data(Data::new(messages.clone()))
+                .service(restore_backup),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/api/backups/../../../../etc/passwd/restore")
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn test_encrypt_blob_decrypt_blob_round_trip() {
+        let key = [7u8; 32];
+        let plaintext = b"sensitive backup contents";
+
+        let blob = encrypt_blob(&key, plaintext).unwrap();
+        assert_ne!(blob, plaintext);
+        assert_eq!(decrypt_blob(&key, &blob).unwrap(), plaintext);
+
+        // A different key must not be able to open the blob.
+        let wrong_key = [9u8; 32];
+        assert!(decrypt_blob(&wrong_key, &blob).is_err());
+
+        // Tampering with the ciphertext must fail AEAD tag verification.
+        let mut tampered = blob.clone();
+        let last = tampered.len() - 1;
+        tampered[last] ^= 0xFF;
+        assert!(decrypt_blob(&key, &tampered).is_err());
+    }
+
+    #[test]
+    fn test_keys_match_constant_time_comparison() {
+        assert!(keys_match(b"secret-key", b"secret-key"));
+        assert!(!keys_match(b"secret-key", b"different-key"));
+        assert!(!keys_match(b"short", b"much-longer-key"));
+        assert!(!keys_match(b"", b"nonempty"));
+    }
+
+    #[actix_rt::test]
+    async fn test_api_key_middleware_rejects_missing_or_wrong_key() {
+        std::env::set_var("ADMIN_API_KEY", "correct-key");
+        let mut app = test::init_service(
+            App::new().wrap(ApiKeyMiddleware::new()).route(
+                "/admin/ping",
+                web::get().to(|| async { HttpResponse::Ok().finish() }),
+            ),
+        )
+        .await;
+
+        let req = test::TestRequest::get().uri("/admin/ping").to_request();
+        let resp = test::call_service(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let req = test::TestRequest::get()
+            .uri("/admin/ping")
+            .insert_header(("x-api-key", "wrong-key"))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let req = test::TestRequest::get()
+            .uri("/admin/ping")
+            .insert_header(("x-api-key", "correct-key"))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    #[actix_rt::test]
+    async fn test_deadline_times_out_slow_handlers() {
+        let mut app = test::init_service(
+            App::new()
+                .wrap(Deadline::new(Duration::from_millis(20)))
+                .route(
+                    "/slow",
+                    web::get().to(|| async {
+                        actix_rt::time::sleep(Duration::from_millis(200)).await;
+                        HttpResponse::Ok().finish()
+                    }),
+                ),
+        )
+        .await;
+
+        let req = test::TestRequest::get().uri("/slow").to_request();
+        let resp = test::call_service(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::REQUEST_TIMEOUT);
+    }
+
+    #[actix_rt::test]
+    async fn test_deadline_passes_through_fast_handlers() {
+        let mut app = test::init_service(
+            App::new()
+                .wrap(Deadline::new(Duration::from_millis(200)))
+                .route("/fast", web::get().to(|| async { HttpResponse::Ok().finish() })),
+        )
+        .await;
+
+        let req = test::TestRequest::get().uri("/fast").to_request();
+        let resp = test::call_service(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    #[actix_rt::test]
+    async fn test_deadline_zero_duration_disables_timeout() {
+        let mut app = test::init_service(
+            App::new().wrap(Deadline::new(Duration::ZERO)).route(
+                "/slow",
+                web::get().to(|| async {
+                    actix_rt::time::sleep(Duration::from_millis(50)).await;
+                    HttpResponse::Ok().finish()
+                }),
+            ),
+        )
+        .await;
+
+        let req = test::TestRequest::get().uri("/slow").to_request();
+        let resp = test::call_service(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    #[actix_rt::test]
+    async fn test_compression_negotiates_gzip_for_large_bodies() {
+        let body = "x".repeat(1024);
+        let mut app = test::init_service(
+            App::new().wrap(Compression::new(256)).route(
+                "/big",
+                web::get().to({
+                    let body = body.clone();
+                    move || {
+                        let body = body.clone();
+                        async move { HttpResponse::Ok().body(body) }
+                    }
+                }),
+            ),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri("/big")
+            .insert_header((header::ACCEPT_ENCODING, "gzip"))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers()
+                .get(header::CONTENT_ENCODING)
+                .unwrap()
+                .to_str()
+                .unwrap(),
+            "gzip"
+        );
+        let compressed = test::read_body(resp).await;
+        assert!(compressed.len() < body.len());
+    }
+
+    #[actix_rt::test]
+    async fn test_compression_skips_small_bodies_and_unnegotiated_requests() {
+        let mut app = test::init_service(
+            App::new()
+                .wrap(Compression::new(256))
+                .route("/small", web::get().to(|| async { HttpResponse::Ok().body("tiny") })),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri("/small")
+            .insert_header((header::ACCEPT_ENCODING, "gzip"))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+        assert!(!resp.headers().contains_key(header::CONTENT_ENCODING));
+        assert_eq!(test::read_body(resp).await, "tiny");
+
+        let req = test::TestRequest::get().uri("/small").to_request();
+        let resp = test::call_service(&mut app, req).await;
+        assert!(!resp.headers().contains_key(header::CONTENT_ENCODING));
+    }
+
+    // Seeds a filesystem-backed BackupSystem with a hand-built incremental
+    // manifest and its chunk objects, bypassing the private
+    // `perform_incremental_backup` path so the test only exercises the
+    // single-manifest restore logic reached through `restore_backup`.
+    #[actix_rt::test]
+    async fn test_restore_backup_from_incremental_manifest() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        std::env::set_var("AWS_BACKUP_BUCKET", "test-bucket");
+        std::env::set_var("AWS_REGION", "us-east-1");
+        std::env::set_var("BACKUP_BACKEND", "filesystem");
+        std::env::set_var("BACKUP_LOCAL_PATH", tmp_dir.path().to_str().unwrap());
+        std::env::set_var("BACKUP_MODE", "incremental");
+        std::env::remove_var("AWS_BACKUP_PREFIX");
+        std::env::remove_var("BACKUP_ENCRYPTION_KEY");
+
+        let config = BackupConfig::from_env().unwrap();
+        let prefix = config.prefix.clone();
+        let messages: Arc<Mutex<Vec<Message>>> = Arc::new(Mutex::new(Vec::new()));
+        let backup_system = Arc::new(BackupSystem::new(messages.clone(), config).await.unwrap());
+
+        let write_object = |key: &str, bytes: &[u8], encrypted: &str| {
+            let path = tmp_dir.path().join(key);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, bytes).unwrap();
+            std::fs::write(
+                format!("{}.meta.json", path.display()),
+                serde_json::to_vec(&std::collections::HashMap::from([(
+                    "encrypted".to_string(),
+                    encrypted.to_string(),
+                )]))
+                .unwrap(),
+            )
+            .unwrap();
+        };
+
+        let messages_to_restore = vec![
+            Message {
+                id: Uuid::new_v4(),
+                created: Utc::now(),
+                content: "First chunk".to_string(),
+                lang: Langs::English,
+                expires: Expiration::Week,
+                title: "Test".to_string(),
+                image_url: None,
+                image_data: None,
+                image_mime_type: None,
+            },
+            Message {
+                id: Uuid::new_v4(),
+                created: Utc::now(),
+                content: "Second chunk".to_string(),
+                lang: Langs::English,
+                expires: Expiration::Week,
+                title: "Test".to_string(),
+                image_url: None,
+                image_data: None,
+                image_mime_type: None,
+            },
+        ];
+
+        let mut chunk_hashes = Vec::new();
+        for message in &messages_to_restore {
+            let json = serde_json::to_vec(message).unwrap();
+            let mut hasher = Sha256::new();
+            hasher.update(&json);
+            let hash = format!("{:x}", hasher.finalize());
+            let compressed = zstd::stream::encode_all(std::io::Cursor::new(json.as_slice()), 3).unwrap();
+            write_object(&format!("{}/chunks/{}.zst", prefix, hash), &compressed, "false");
+            chunk_hashes.push(hash);
+        }
+
+        let manifest_key = format!("{}/manifests/manifest_20260101_000000.json", prefix);
+        let manifest_json = serde_json::to_vec(&serde_json::json!({
+            "timestamp": Utc::now(),
+            "chunk_hashes": chunk_hashes,
+            "previous_manifest_key": null,
+        }))
+        .unwrap();
+        write_object(&manifest_key, &manifest_json, "false");
+
+        let mut app = test::init_service(
+            App::new()
+                .app_data(Data::new(backup_system.clone()))
+                .app_data(Data::new(messages.clone()))
+                .service(restore_backup),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/api/backups/{}/restore", manifest_key))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let messages = messages.lock().unwrap();
+        assert_eq!(messages.len(), 2);
+        assert!(messages.iter().any(|m| m.content == "First chunk"));
+        assert!(messages.iter().any(|m| m.content == "Second chunk"));
+    }
+
+    // perform_full_backup's streamed temp-file upload (src/backup.rs) isn't
+    // reachable from here since it's a private method with no S3 double in
+    // this test harness; this instead seeds the filesystem backend with the
+    // `checksum_sha256` metadata that streaming path writes, to cover the
+    // checksum verification `restore_from_full_backup` runs against it.
+    #[actix_rt::test]
+    async fn test_restore_backup_verifies_checksum() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        std::env::set_var("AWS_BACKUP_BUCKET", "test-bucket");
+        std::env::set_var("AWS_REGION", "us-east-1");
+        std::env::set_var("BACKUP_BACKEND", "filesystem");
+        std::env::set_var("BACKUP_LOCAL_PATH", tmp_dir.path().to_str().unwrap());
+        std::env::remove_var("AWS_BACKUP_PREFIX");
+        std::env::remove_var("BACKUP_MODE");
+        std::env::remove_var("BACKUP_ENCRYPTION_KEY");
+
+        let config = BackupConfig::from_env().unwrap();
+        let prefix = config.prefix.clone();
+        let messages: Arc<Mutex<Vec<Message>>> = Arc::new(Mutex::new(Vec::new()));
+        let backup_system = Arc::new(BackupSystem::new(messages.clone(), config).await.unwrap());
+
+        let backed_up: Vec<Message> = (0..50)
+            .map(|i| Message {
+                id: Uuid::new_v4(),
+                created: Utc::now(),
+                content: format!("Backed up message {i}"),
+                lang: Langs::English,
+                expires: Expiration::Week,
+                title: "Test".to_string(),
+                image_url: None,
+                image_data: None,
+                image_mime_type: None,
+            })
+            .collect();
+        let json = serde_json::to_vec(&backed_up).unwrap();
+        let mut hasher = Sha256::new();
+        hasher.update(&json);
+        let checksum = format!("{:x}", hasher.finalize());
+        let compressed = zstd::stream::encode_all(std::io::Cursor::new(json.as_slice()), 3).unwrap();
+
+        let write_backup = |name: &str, checksum: &str| {
+            let backup_key = format!("{}/{}.json.zst", prefix, name);
+            let backup_path = tmp_dir.path().join(&backup_key);
+            std::fs::create_dir_all(backup_path.parent().unwrap()).unwrap();
+            std::fs::write(&backup_path, &compressed).unwrap();
+            std::fs::write(
+                format!("{}.meta.json", backup_path.display()),
+                serde_json::to_vec(&std::collections::HashMap::from([
+                    ("message_count".to_string(), backed_up.len().to_string()),
+                    ("encrypted".to_string(), "false".to_string()),
+                    ("checksum_sha256".to_string(), checksum.to_string()),
+                ]))
+                .unwrap(),
+            )
+            .unwrap();
+            backup_key
+        };
+
+        let good_key = write_backup("backup_good", &checksum);
+        let corrupt_key = write_backup("backup_corrupt", "0000000000000000000000000000000000000000000000000000000000000000");
+
+        let mut app = test::init_service(
+            App::new()
+                .app_data(Data::new(backup_system.clone()))
+                .app_data(Data::new(messages.clone()))
+                .service(restore_backup),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/api/backups/{}/restore", good_key))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(messages.lock().unwrap().len(), 50);
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/api/backups/{}/restore", corrupt_key))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
 }
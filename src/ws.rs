@@ -0,0 +1,106 @@
+use actix::{Actor, AsyncContext, StreamHandler};
+use actix_web::{
+    get,
+    web::{Data, Payload},
+    Error, HttpRequest, HttpResponse,
+};
+use actix_web_actors::ws;
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast;
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
+use tokio_stream::wrappers::BroadcastStream;
+
+use crate::messages::{Message, MessageSummary};
+
+const MAX_FRAME_BYTES: usize = 1024 * 1024;
+
+/// A live connection to the message feed. Sends the current snapshot on
+/// connect, then forwards each newly posted `Message` as its own text frame.
+pub struct MessageFeed {
+    messages: Data<Arc<Mutex<Vec<Message>>>>,
+    feed: Data<broadcast::Sender<Message>>,
+}
+
+impl MessageFeed {
+    pub fn new(
+        messages: Data<Arc<Mutex<Vec<Message>>>>,
+        feed: Data<broadcast::Sender<Message>>,
+    ) -> Self {
+        Self { messages, feed }
+    }
+}
+
+impl Actor for MessageFeed {
+    type Context = ws::WebsocketContext<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        let snapshot = match self.messages.lock() {
+            Ok(repo) => repo.clone(),
+            Err(_) => {
+                ctx.close(Some(ws::CloseCode::Error.into()));
+                ctx.stop();
+                return;
+            }
+        };
+
+        for message in &snapshot {
+            send_message(ctx, message);
+        }
+
+        ctx.add_stream(BroadcastStream::new(self.feed.subscribe()));
+    }
+}
+
+fn send_message(ctx: &mut ws::WebsocketContext<MessageFeed>, message: &Message) {
+    let summary = MessageSummary::from(message);
+    match serde_json::to_string(&summary) {
+        Ok(body) if body.len() <= MAX_FRAME_BYTES => ctx.text(body),
+        Ok(_) => log::warn!("dropping oversized message feed frame for {}", message.id),
+        Err(e) => log::error!("failed to serialize message for feed: {}", e),
+    }
+}
+
+// Forwards broadcast messages to the socket, closing the connection with a
+// policy-violation close code if this client falls too far behind to keep up.
+impl StreamHandler<Result<Message, BroadcastStreamRecvError>> for MessageFeed {
+    fn handle(&mut self, item: Result<Message, BroadcastStreamRecvError>, ctx: &mut Self::Context) {
+        match item {
+            Ok(message) => send_message(ctx, &message),
+            Err(BroadcastStreamRecvError::Lagged(_)) => {
+                ctx.close(Some(ws::CloseReason {
+                    code: ws::CloseCode::Policy,
+                    description: Some("client fell behind the message feed".to_string()),
+                }));
+                ctx.stop();
+            }
+        }
+    }
+}
+
+// The feed is read-only from the client's perspective; we only need to
+// answer pings and honor close requests.
+impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for MessageFeed {
+    fn handle(&mut self, msg: Result<ws::Message, ws::ProtocolError>, ctx: &mut Self::Context) {
+        match msg {
+            Ok(ws::Message::Ping(bytes)) => ctx.pong(&bytes),
+            Ok(ws::Message::Close(reason)) => {
+                ctx.close(reason);
+                ctx.stop();
+            }
+            Ok(_) => {}
+            Err(_) => ctx.stop(),
+        }
+    }
+}
+
+// Endpoint to upgrade to a websocket connection and stream new messages as
+// they're posted, after an initial snapshot of the current message set.
+#[get("/api/messages/feed")]
+pub async fn message_feed(
+    req: HttpRequest,
+    stream: Payload,
+    messages: Data<Arc<Mutex<Vec<Message>>>>,
+    feed: Data<broadcast::Sender<Message>>,
+) -> Result<HttpResponse, Error> {
+    ws::start(MessageFeed::new(messages, feed), &req, stream)
+}